@@ -0,0 +1,167 @@
+//! A small HTTP client for the LongPort OpenAPI, handling signing, retries,
+//! response compression and the OpenAPI response envelope.
+
+mod qs;
+mod request;
+mod signature;
+mod timestamp;
+
+use std::fmt;
+
+use reqwest::{
+    Method, StatusCode,
+    header::HeaderMap,
+};
+
+pub use request::{
+    Authenticator, ByteStream, FromPayload, FromStreamingPayload, FrozenRequest, Json, Jitter,
+    LongPortAuthenticator, RequestBuilder, RetryPolicy, ToPayload,
+};
+pub use timestamp::Timestamp;
+
+/// A specialized `Result` for HTTP client operations.
+pub type HttpClientResult<T> = Result<T, HttpClientError>;
+
+/// An error produced by the HTTP client.
+#[derive(Debug)]
+pub enum HttpClientError {
+    /// The configured application key is not a valid header value.
+    InvalidApiKey,
+    /// The configured access token is not a valid header value.
+    InvalidAccessToken,
+    /// The request body could not be serialized.
+    SerializeRequestBody(String),
+    /// The query string could not be serialized.
+    SerializeQueryString(String),
+    /// A transport-level error.
+    Http(Box<dyn std::error::Error + Send + Sync>),
+    /// The request did not complete within the timeout.
+    RequestTimeout,
+    /// The response envelope was successful but carried no data.
+    UnexpectedResponse,
+    /// The OpenAPI envelope reported a business error.
+    OpenApi {
+        /// The OpenAPI error code.
+        code: i32,
+        /// The human-readable message.
+        message: String,
+        /// The server-assigned trace id.
+        trace_id: String,
+    },
+    /// The response body could not be deserialized.
+    DeserializeResponseBody(String),
+    /// The server returned an unsuccessful HTTP status without a usable
+    /// envelope.
+    BadStatus(StatusCode),
+    /// The compressed response body could not be inflated.
+    Decompression(String),
+}
+
+impl fmt::Display for HttpClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HttpClientError::InvalidApiKey => write!(f, "invalid api key"),
+            HttpClientError::InvalidAccessToken => write!(f, "invalid access token"),
+            HttpClientError::SerializeRequestBody(err) => {
+                write!(f, "failed to serialize request body: {err}")
+            }
+            HttpClientError::SerializeQueryString(err) => {
+                write!(f, "failed to serialize query string: {err}")
+            }
+            HttpClientError::Http(err) => write!(f, "http error: {err}"),
+            HttpClientError::RequestTimeout => write!(f, "request timeout"),
+            HttpClientError::UnexpectedResponse => write!(f, "unexpected response"),
+            HttpClientError::OpenApi {
+                code,
+                message,
+                trace_id,
+            } => write!(f, "openapi error {code}: {message} (trace_id={trace_id})"),
+            HttpClientError::DeserializeResponseBody(err) => {
+                write!(f, "failed to deserialize response body: {err}")
+            }
+            HttpClientError::BadStatus(status) => write!(f, "bad status: {status}"),
+            HttpClientError::Decompression(err) => {
+                write!(f, "failed to decompress response body: {err}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for HttpClientError {}
+
+/// Configuration for an [`HttpClient`].
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// Override for the base URL. When unset, the public endpoint is used.
+    pub http_url: Option<String>,
+    /// The application key.
+    pub app_key: String,
+    /// The access token.
+    pub access_token: String,
+    /// The application secret.
+    pub app_secret: String,
+    /// Whether to negotiate response compression with `Accept-Encoding`.
+    pub accept_compression: bool,
+    /// The retry policy applied to requests by default.
+    pub retry_policy: RetryPolicy,
+}
+
+impl Config {
+    /// Create a configuration from the three credential components, with the
+    /// other fields defaulted.
+    pub fn new(
+        app_key: impl Into<String>,
+        access_token: impl Into<String>,
+        app_secret: impl Into<String>,
+    ) -> Self {
+        Self {
+            http_url: None,
+            app_key: app_key.into(),
+            access_token: access_token.into(),
+            app_secret: app_secret.into(),
+            accept_compression: true,
+            retry_policy: RetryPolicy::default(),
+        }
+    }
+}
+
+/// The HTTP client.
+pub struct HttpClient {
+    pub(crate) http_cli: reqwest::Client,
+    pub(crate) config: Config,
+    pub(crate) default_headers: HeaderMap,
+    pub(crate) authenticator: Box<dyn Authenticator>,
+}
+
+impl HttpClient {
+    /// Create a client using the default [`LongPortAuthenticator`] built from
+    /// the credentials in `config`.
+    pub fn new(config: Config) -> Self {
+        let authenticator = Box::new(LongPortAuthenticator {
+            app_key: config.app_key.clone(),
+            access_token: config.access_token.clone(),
+            app_secret: config.app_secret.clone(),
+        });
+        Self::with_authenticator(config, authenticator)
+    }
+
+    /// Create a client with a custom [`Authenticator`].
+    pub fn with_authenticator(config: Config, authenticator: Box<dyn Authenticator>) -> Self {
+        Self {
+            http_cli: reqwest::Client::new(),
+            config,
+            default_headers: HeaderMap::new(),
+            authenticator,
+        }
+    }
+
+    /// Begin building a request.
+    pub fn request(&self, method: Method, path: impl Into<String>) -> RequestBuilder<'_, (), (), ()> {
+        RequestBuilder::new(self, method, path)
+    }
+}
+
+/// Whether the client should target the mainland China endpoint.
+pub(crate) async fn is_cn() -> bool {
+    false
+}
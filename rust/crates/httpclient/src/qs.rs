@@ -0,0 +1,12 @@
+use serde::Serialize;
+
+use crate::{HttpClientError, HttpClientResult};
+
+/// Render `value` as an `application/x-www-form-urlencoded` query string.
+pub fn to_string<T>(value: &T) -> HttpClientResult<String>
+where
+    T: Serialize + ?Sized,
+{
+    serde_urlencoded::to_string(value)
+        .map_err(|err| HttpClientError::SerializeQueryString(err.to_string()))
+}
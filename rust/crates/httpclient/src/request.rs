@@ -3,9 +3,14 @@ use std::{
     error::Error,
     fmt::Debug,
     marker::PhantomData,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
     time::{Duration, Instant},
 };
 
+use futures_util::stream::{BoxStream, Stream};
+
 use reqwest::{
     Method, StatusCode,
     header::{HeaderMap, HeaderName, HeaderValue},
@@ -26,6 +31,314 @@ const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
 const RETRY_COUNT: usize = 5;
 const RETRY_INITIAL_DELAY: Duration = Duration::from_millis(100);
 const RETRY_FACTOR: f32 = 2.0;
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// Jitter applied to the computed backoff delay between retries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Jitter {
+    /// Use the raw exponential delay with no randomization.
+    None,
+    /// Sample uniformly in `[0, delay]`.
+    Full,
+    /// Decorrelated jitter: `min(max_delay, random_between(base, prev * factor))`.
+    Decorrelated,
+}
+
+/// Controls how [`RequestBuilder::send`] retries failed requests.
+///
+/// A policy is configured on [`HttpClient::config`] and can be overridden per
+/// request with [`RequestBuilder::retry_policy`].
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Maximum number of retries after the initial attempt.
+    pub max_attempts: usize,
+    /// Starting backoff delay: the first retry's delay with [`Jitter::None`],
+    /// and the lower bound of every delay with [`Jitter::Decorrelated`].
+    pub base_delay: Duration,
+    /// Multiplier applied to the delay on each successive retry.
+    pub factor: f32,
+    /// Upper bound for any single backoff delay.
+    pub max_delay: Duration,
+    /// How the computed delay is randomized.
+    pub jitter: Jitter,
+    /// Retry on `5xx` responses (other than the always-retried `429`).
+    pub retry_on_server_error: bool,
+    /// Retry on connection and timeout errors.
+    pub retry_on_connection_error: bool,
+    /// Retry non-idempotent methods (`POST`/`PUT`/`DELETE`). Off by default so
+    /// order-placing requests aren't accidentally duplicated.
+    pub retry_non_idempotent: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: RETRY_COUNT,
+            base_delay: RETRY_INITIAL_DELAY,
+            factor: RETRY_FACTOR,
+            max_delay: RETRY_MAX_DELAY,
+            jitter: Jitter::Decorrelated,
+            retry_on_server_error: false,
+            retry_on_connection_error: false,
+            retry_non_idempotent: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Whether `method` may be retried under this policy.
+    fn method_is_retryable(&self, method: &Method) -> bool {
+        match *method {
+            Method::GET | Method::HEAD | Method::OPTIONS | Method::TRACE => true,
+            _ => self.retry_non_idempotent,
+        }
+    }
+
+    /// Whether a failed attempt should be retried, ignoring the attempt count.
+    fn should_retry(&self, failed: &FailedAttempt) -> bool {
+        if failed.is_connection_error {
+            return self.retry_on_connection_error;
+        }
+        match failed.status {
+            Some(StatusCode::TOO_MANY_REQUESTS) => true,
+            Some(status) if status.is_server_error() => self.retry_on_server_error,
+            _ => false,
+        }
+    }
+
+    /// Compute the backoff delay for a zero-based retry `attempt`, given the
+    /// delay used by the previous attempt (for decorrelated jitter).
+    fn next_delay(&self, attempt: usize, prev_delay: Duration) -> Duration {
+        match self.jitter {
+            Jitter::None => self.exponential(attempt),
+            Jitter::Full => random_duration(Duration::ZERO, self.exponential(attempt)),
+            Jitter::Decorrelated => {
+                let high = Duration::from_secs_f32(prev_delay.as_secs_f32() * self.factor)
+                    .min(self.max_delay)
+                    .max(self.base_delay);
+                random_duration(self.base_delay, high)
+            }
+        }
+    }
+
+    fn exponential(&self, attempt: usize) -> Duration {
+        let delay = self.base_delay.as_secs_f32() * self.factor.powi(attempt as i32);
+        Duration::from_secs_f32(delay).min(self.max_delay)
+    }
+}
+
+/// Sample a duration uniformly in `[low, high]`.
+fn random_duration(low: Duration, high: Duration) -> Duration {
+    if high <= low {
+        return low;
+    }
+    let span = (high - low).as_secs_f64();
+    low + Duration::from_secs_f64(span * rand::random::<f64>())
+}
+
+/// Parse a `Retry-After` header value, either an integer number of seconds or
+/// an HTTP-date.
+fn parse_retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(reqwest::header::RETRY_AFTER)?.to_str().ok()?;
+    if let Ok(secs) = value.trim().parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let when = httpdate::parse_http_date(value.trim()).ok()?;
+    when.duration_since(std::time::SystemTime::now()).ok()
+}
+
+/// The response body as received from the transport, before decompression.
+enum RawBody {
+    /// An unencoded body, already decoded as text.
+    Text(String),
+    /// A `gzip`-encoded body.
+    Gzip(bytes::Bytes),
+    /// A `deflate`-encoded body.
+    Deflate(bytes::Bytes),
+}
+
+/// Inflate a `gzip`-encoded payload to a UTF-8 string.
+fn inflate_gzip(data: &[u8]) -> std::io::Result<String> {
+    use std::io::Read;
+
+    let mut decoder = flate2::read::GzDecoder::new(data);
+    let mut out = String::new();
+    decoder.read_to_string(&mut out)?;
+    Ok(out)
+}
+
+/// Inflate a `deflate`-encoded payload to a UTF-8 string.
+fn inflate_deflate(data: &[u8]) -> std::io::Result<String> {
+    use std::io::Read;
+
+    let mut decoder = flate2::read::ZlibDecoder::new(data);
+    let mut out = String::new();
+    decoder.read_to_string(&mut out)?;
+    Ok(out)
+}
+
+/// A single failed send attempt, carrying enough context for the retry loop to
+/// decide whether and how long to back off.
+struct FailedAttempt {
+    error: HttpClientError,
+    status: Option<StatusCode>,
+    retry_after: Option<Duration>,
+    is_connection_error: bool,
+}
+
+impl From<HttpClientError> for FailedAttempt {
+    /// Setup errors (bad credentials, body serialization) are fatal and never
+    /// retried.
+    fn from(error: HttpClientError) -> Self {
+        FailedAttempt {
+            error,
+            status: None,
+            retry_after: None,
+            is_connection_error: false,
+        }
+    }
+}
+
+/// Execute a fully-built request, decode and decompress the body, and parse the
+/// [`OpenApiResponse`] envelope into `R`.
+async fn send_request<R>(
+    http_cli: &reqwest::Client,
+    request: reqwest::Request,
+) -> Result<R, FailedAttempt>
+where
+    R: FromPayload,
+{
+    let s = Instant::now();
+
+    let result = tokio::time::timeout(REQUEST_TIMEOUT, async move {
+        let resp = http_cli.execute(request).await?;
+        let status = resp.status();
+        let trace_id = resp
+            .headers()
+            .get("x-trace-id")
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        let retry_after = parse_retry_after(resp.headers());
+        let body = match resp
+            .headers()
+            .get(reqwest::header::CONTENT_ENCODING)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.trim().to_ascii_lowercase())
+        {
+            Some(encoding) if encoding == "gzip" => RawBody::Gzip(resp.bytes().await?),
+            Some(encoding) if encoding == "deflate" => RawBody::Deflate(resp.bytes().await?),
+            _ => RawBody::Text(resp.text().await?),
+        };
+        Ok::<_, reqwest::Error>((status, trace_id, retry_after, body))
+    })
+    .await;
+
+    let (status, trace_id, retry_after, body) = match result {
+        Ok(Ok(resp)) => resp,
+        Ok(Err(err)) => {
+            return Err(FailedAttempt {
+                error: HttpClientError::Http(err.into()),
+                status: None,
+                retry_after: None,
+                is_connection_error: true,
+            });
+        }
+        Err(_) => {
+            return Err(FailedAttempt {
+                error: HttpClientError::RequestTimeout,
+                status: None,
+                retry_after: None,
+                is_connection_error: true,
+            });
+        }
+    };
+
+    let decompress_err = |err: std::io::Error| FailedAttempt {
+        error: HttpClientError::Decompression(err.to_string()),
+        status: Some(status),
+        retry_after,
+        is_connection_error: false,
+    };
+    let text = match body {
+        RawBody::Text(text) => text,
+        RawBody::Gzip(bytes) => inflate_gzip(&bytes).map_err(decompress_err)?,
+        RawBody::Deflate(bytes) => inflate_deflate(&bytes).map_err(decompress_err)?,
+    };
+
+    tracing::info!(duration = ?s.elapsed(), body = %text.as_str(), "http response");
+
+    let resp = match serde_json::from_str::<OpenApiResponse>(&text) {
+        Ok(resp) if resp.code == 0 => resp.data.ok_or(HttpClientError::UnexpectedResponse),
+        Ok(resp) => Err(HttpClientError::OpenApi {
+            code: resp.code,
+            message: resp.message,
+            trace_id,
+        }),
+        Err(err) if status == StatusCode::OK => {
+            Err(HttpClientError::DeserializeResponseBody(err.to_string()))
+        }
+        Err(_) => Err(HttpClientError::BadStatus(status)),
+    }
+    .map_err(|error| FailedAttempt {
+        error,
+        status: Some(status),
+        retry_after,
+        is_connection_error: false,
+    })?;
+
+    R::parse_from_bytes(resp.get().as_bytes()).map_err(|err| FailedAttempt {
+        error: HttpClientError::DeserializeResponseBody(err.to_string()),
+        status: Some(status),
+        retry_after,
+        is_connection_error: false,
+    })
+}
+
+/// Drive `attempt_fn` under `policy`, backing off between retries. Shared by
+/// [`RequestBuilder::send`] and [`FrozenRequest::send`].
+async fn run_with_retry<R, F, Fut>(
+    method: &Method,
+    policy: &RetryPolicy,
+    mut attempt_fn: F,
+) -> HttpClientResult<R>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<R, FailedAttempt>>,
+{
+    let retryable_method = policy.method_is_retryable(method);
+    let mut prev_delay = policy.base_delay;
+    let mut attempt = 0usize;
+
+    loop {
+        match attempt_fn().await {
+            Ok(resp) => return Ok(resp),
+            Err(failed) => {
+                if attempt >= policy.max_attempts
+                    || !retryable_method
+                    || !policy.should_retry(&failed)
+                {
+                    return Err(failed.error);
+                }
+
+                // A `Retry-After` header wins over the computed backoff and is
+                // honored as-is: the server told us exactly how long to wait, so
+                // the backoff `max_delay` cap must not truncate it.
+                let delay = match failed.retry_after {
+                    Some(delay) => delay,
+                    None => {
+                        let delay = policy.next_delay(attempt, prev_delay);
+                        prev_delay = delay;
+                        delay
+                    }
+                };
+
+                attempt += 1;
+                tokio::time::sleep(delay).await;
+            }
+        }
+    }
+}
 
 /// A JSON payload
 #[derive(Debug)]
@@ -109,6 +422,61 @@ impl ToPayload for () {
     }
 }
 
+/// Represents a type that can be constructed from a streaming response body.
+///
+/// This is the streaming counterpart to [`FromPayload`]: the response type is
+/// selected through the typed-builder API via
+/// [`RequestBuilder::response_stream`], and the body is consumed incrementally
+/// via [`RequestBuilder::send_stream`] instead of being buffered whole.
+pub trait FromStreamingPayload: Send + 'static {
+    // A streaming body is consumed from a single task, so `Sync` is not
+    // required (unlike [`FromPayload`]).
+
+    /// Build the value from the response status and a stream of body chunks.
+    fn from_stream(
+        status: StatusCode,
+        stream: BoxStream<'static, reqwest::Result<bytes::Bytes>>,
+    ) -> Self;
+}
+
+/// The default streaming response body yielded by
+/// [`RequestBuilder::send_stream`].
+///
+/// Yields the raw body chunks exactly as they arrive from the transport.
+pub struct ByteStream {
+    status: StatusCode,
+    inner: BoxStream<'static, reqwest::Result<bytes::Bytes>>,
+}
+
+impl ByteStream {
+    /// The HTTP status of the response.
+    #[inline]
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+}
+
+impl Stream for ByteStream {
+    type Item = reqwest::Result<bytes::Bytes>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
+
+impl FromStreamingPayload for ByteStream {
+    #[inline]
+    fn from_stream(
+        status: StatusCode,
+        stream: BoxStream<'static, reqwest::Result<bytes::Bytes>>,
+    ) -> Self {
+        ByteStream {
+            status,
+            inner: stream,
+        }
+    }
+}
+
 #[derive(Deserialize)]
 struct OpenApiResponse {
     code: i32,
@@ -116,6 +484,67 @@ struct OpenApiResponse {
     data: Option<Box<serde_json::value::RawValue>>,
 }
 
+/// Signs outgoing requests before they are sent.
+///
+/// The [default][`LongPortAuthenticator`] implementation is the LongPort
+/// HMAC-SHA256 scheme; custom implementations can provide alternative
+/// credentials (token-only mode, an external HSM/KMS signer, a test double,
+/// ...) without forking the crate.
+pub trait Authenticator: Send + Sync {
+    /// Sign `request`, injecting whatever authentication headers the scheme
+    /// requires. `timestamp` is the per-call request time; advertising it on
+    /// the request (the default scheme sends `X-Timestamp`) is up to the scheme.
+    fn sign(
+        &self,
+        request: &mut reqwest::Request,
+        timestamp: Timestamp,
+    ) -> Result<(), HttpClientError>;
+}
+
+/// The default LongPort HMAC-SHA256 signing scheme.
+pub struct LongPortAuthenticator {
+    /// The application key, sent as `X-Api-Key`.
+    pub app_key: String,
+    /// The access token, sent as `Authorization`.
+    pub access_token: String,
+    /// The application secret used to derive the signature.
+    pub app_secret: String,
+}
+
+impl Authenticator for LongPortAuthenticator {
+    fn sign(
+        &self,
+        request: &mut reqwest::Request,
+        timestamp: Timestamp,
+    ) -> Result<(), HttpClientError> {
+        let app_key_value =
+            HeaderValue::from_str(&self.app_key).map_err(|_| HttpClientError::InvalidApiKey)?;
+        let access_token_value = HeaderValue::from_str(&self.access_token)
+            .map_err(|_| HttpClientError::InvalidAccessToken)?;
+
+        let headers = request.headers_mut();
+        headers.insert("X-Api-Key", app_key_value);
+        headers.insert("Authorization", access_token_value);
+        headers.insert(
+            "X-Timestamp",
+            HeaderValue::from_str(&timestamp.to_string()).expect("valid timestamp"),
+        );
+
+        let sign = signature(SignatureParams {
+            request: &*request,
+            app_key: &self.app_key,
+            access_token: Some(&self.access_token),
+            app_secret: &self.app_secret,
+            timestamp,
+        });
+        request.headers_mut().insert(
+            "X-Api-Signature",
+            HeaderValue::from_maybe_shared(sign).expect("valid signature"),
+        );
+        Ok(())
+    }
+}
+
 /// A request builder
 pub struct RequestBuilder<'a, T, Q, R> {
     client: &'a HttpClient,
@@ -124,6 +553,7 @@ pub struct RequestBuilder<'a, T, Q, R> {
     headers: HeaderMap,
     body: Option<T>,
     query_params: Option<Q>,
+    retry_policy: Option<RetryPolicy>,
     mark_resp: PhantomData<R>,
 }
 
@@ -136,6 +566,7 @@ impl<'a> RequestBuilder<'a, (), (), ()> {
             headers: Default::default(),
             body: None,
             query_params: None,
+            retry_policy: None,
             mark_resp: PhantomData,
         }
     }
@@ -155,6 +586,7 @@ impl<'a, T, Q, R> RequestBuilder<'a, T, Q, R> {
             headers: self.headers,
             body: Some(body),
             query_params: self.query_params,
+            retry_policy: self.retry_policy,
             mark_resp: self.mark_resp,
         }
     }
@@ -187,6 +619,7 @@ impl<'a, T, Q, R> RequestBuilder<'a, T, Q, R> {
             headers: self.headers,
             body: self.body,
             query_params: Some(params),
+            retry_policy: self.retry_policy,
             mark_resp: self.mark_resp,
         }
     }
@@ -204,16 +637,44 @@ impl<'a, T, Q, R> RequestBuilder<'a, T, Q, R> {
             headers: self.headers,
             body: self.body,
             query_params: self.query_params,
+            retry_policy: self.retry_policy,
+            mark_resp: PhantomData,
+        }
+    }
+
+    /// Set the streaming response body type, for use with
+    /// [`send_stream`][Self::send_stream].
+    #[must_use]
+    pub fn response_stream<R2>(self) -> RequestBuilder<'a, T, Q, R2>
+    where
+        R2: FromStreamingPayload,
+    {
+        RequestBuilder {
+            client: self.client,
+            method: self.method,
+            path: self.path,
+            headers: self.headers,
+            body: self.body,
+            query_params: self.query_params,
+            retry_policy: self.retry_policy,
             mark_resp: PhantomData,
         }
     }
+
+    /// Override the [`RetryPolicy`] for this request.
+    ///
+    /// When unset, the policy configured on [`HttpClient::config`] is used.
+    #[must_use]
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
+        self
+    }
 }
 
 impl<T, Q, R> RequestBuilder<'_, T, Q, R>
 where
     T: ToPayload,
     Q: Serialize + Send,
-    R: FromPayload,
 {
     async fn http_url(&self) -> &str {
         if let Some(url) = self.client.config.http_url.as_deref() {
@@ -223,11 +684,14 @@ where
         if is_cn().await { HTTP_URL_CN } else { HTTP_URL }
     }
 
-    async fn do_send(&self) -> HttpClientResult<R> {
+    /// Build the fully-signed request, including headers, body, query string
+    /// and authentication. Shared by the buffered and streaming send paths.
+    async fn build_request(&self) -> Result<reqwest::Request, HttpClientError> {
         let HttpClient {
             http_cli,
             config,
             default_headers,
+            authenticator,
         } = &self.client;
         let timestamp = self
             .headers
@@ -235,10 +699,6 @@ where
             .and_then(|value| value.to_str().ok())
             .and_then(|value| value.parse().ok())
             .unwrap_or_else(Timestamp::now);
-        let app_key_value =
-            HeaderValue::from_str(&config.app_key).map_err(|_| HttpClientError::InvalidApiKey)?;
-        let access_token_value = HeaderValue::from_str(&config.access_token)
-            .map_err(|_| HttpClientError::InvalidAccessToken)?;
 
         let url = self.http_url().await;
         let mut request_builder = http_cli
@@ -246,11 +706,13 @@ where
             .headers(default_headers.clone())
             .headers(self.headers.clone())
             .header("User-Agent", USER_AGENT)
-            .header("X-Api-Key", app_key_value)
-            .header("Authorization", access_token_value)
-            .header("X-Timestamp", timestamp.to_string())
             .header("Content-Type", "application/json; charset=utf-8");
 
+        // negotiate response compression unless opted out
+        if config.accept_compression {
+            request_builder = request_builder.header("Accept-Encoding", "gzip, deflate");
+        }
+
         // set the request body
         if let Some(body) = &self.body {
             let body = body
@@ -267,18 +729,22 @@ where
             request.url_mut().set_query(Some(&query_string));
         }
 
-        // signature the request
-        let sign = signature(SignatureParams {
-            request: &request,
-            app_key: &config.app_key,
-            access_token: Some(&config.access_token),
-            app_secret: &config.app_secret,
-            timestamp,
-        });
-        request.headers_mut().insert(
-            "X-Api-Signature",
-            HeaderValue::from_maybe_shared(sign).expect("valid signature"),
-        );
+        // sign the request with the configured authenticator
+        authenticator.sign(&mut request, timestamp)?;
+
+        Ok(request)
+    }
+}
+
+impl<T, Q, R> RequestBuilder<'_, T, Q, R>
+where
+    T: ToPayload,
+    Q: Serialize + Send,
+    R: FromPayload,
+{
+    async fn try_send(&self) -> Result<R, FailedAttempt> {
+        let http_cli = &self.client.http_cli;
+        let request = self.build_request().await?;
 
         if let Some(body) = &self.body {
             tracing::info!(method = %request.method(), url = %request.url(), body = ?body, "http request");
@@ -286,73 +752,416 @@ where
             tracing::info!(method = %request.method(), url = %request.url(), "http request");
         }
 
-        let s = Instant::now();
+        send_request(http_cli, request).await
+    }
+
+    /// Send request and get the response, retrying according to the effective
+    /// [`RetryPolicy`].
+    pub async fn send(self) -> HttpClientResult<R> {
+        let policy = self
+            .retry_policy
+            .clone()
+            .unwrap_or_else(|| self.client.config.retry_policy.clone());
+        run_with_retry(&self.method, &policy, || self.try_send()).await
+    }
+}
 
-        // send request
-        let (status, trace_id, text) = tokio::time::timeout(REQUEST_TIMEOUT, async move {
-            let resp = http_cli
-                .execute(request)
-                .await
-                .map_err(|err| HttpClientError::Http(err.into()))?;
-            let status = resp.status();
-            let trace_id = resp
-                .headers()
-                .get("x-trace-id")
-                .and_then(|value| value.to_str().ok())
-                .unwrap_or_default()
-                .to_string();
+impl<T, Q, R> RequestBuilder<'_, T, Q, R>
+where
+    T: ToPayload,
+    Q: Serialize + Send,
+    R: FromStreamingPayload,
+{
+    /// Send the request and stream the response body incrementally instead of
+    /// buffering the whole payload.
+    ///
+    /// Only the *HTTP status* is peeked before streaming: a non-success status
+    /// is buffered and surfaced as the usual [`HttpClientError`], but the
+    /// `code`-level [`OpenApiResponse`] envelope is **not** honored. An error
+    /// envelope returned as `HTTP 200` (`code != 0`) is streamed to the caller
+    /// as raw JSON bytes, so callers must inspect the payload themselves rather
+    /// than treating a successful stream as a successful business response.
+    ///
+    /// The request is always sent with `Accept-Encoding: identity`: the stream
+    /// is handed through verbatim and is not decompressed (unlike the buffered
+    /// [`send`][Self::send] path), so the caller always receives plain bytes.
+    ///
+    /// On success the body is handed to [`FromStreamingPayload::from_stream`],
+    /// skipping the envelope entirely. Retries are not applied, as the body is
+    /// consumed by the caller.
+    pub async fn send_stream(self) -> HttpClientResult<R> {
+        let http_cli = &self.client.http_cli;
+        let mut request = self.build_request().await?;
+
+        // streaming does not decompress, so never negotiate compression
+        request.headers_mut().insert(
+            reqwest::header::ACCEPT_ENCODING,
+            HeaderValue::from_static("identity"),
+        );
+
+        if let Some(body) = &self.body {
+            tracing::info!(method = %request.method(), url = %request.url(), body = ?body, "http stream request");
+        } else {
+            tracing::info!(method = %request.method(), url = %request.url(), "http stream request");
+        }
+
+        let resp = http_cli
+            .execute(request)
+            .await
+            .map_err(|err| HttpClientError::Http(err.into()))?;
+        let status = resp.status();
+        let trace_id = resp
+            .headers()
+            .get("x-trace-id")
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+
+        // peek the status before streaming, preserving the envelope contract
+        if !status.is_success() {
             let text = resp
                 .text()
                 .await
                 .map_err(|err| HttpClientError::Http(err.into()))?;
-            Ok::<_, HttpClientError>((status, trace_id, text))
+            return Err(match serde_json::from_str::<OpenApiResponse>(&text) {
+                Ok(resp) => HttpClientError::OpenApi {
+                    code: resp.code,
+                    message: resp.message,
+                    trace_id,
+                },
+                Err(_) => HttpClientError::BadStatus(status),
+            });
+        }
+
+        Ok(R::from_stream(status, Box::pin(resp.bytes_stream())))
+    }
+}
+
+impl<'a, T, Q, R> RequestBuilder<'a, T, Q, R>
+where
+    T: ToPayload,
+    Q: Serialize + Send,
+{
+    /// Freeze this builder into a reusable [`FrozenRequest`].
+    ///
+    /// The body is serialized and the query string rendered once, up front;
+    /// each subsequent send re-derives only the per-call timestamp and
+    /// signature. Useful for scheduled re-polling of quotes/positions without
+    /// rebuilding the whole builder on every call.
+    pub fn freeze(self) -> HttpClientResult<FrozenRequest<'a, R>> {
+        let body = match &self.body {
+            Some(body) => Some(Arc::new(
+                body.to_bytes()
+                    .map_err(|err| HttpClientError::SerializeRequestBody(err.to_string()))?,
+            )),
+            None => None,
+        };
+        let query_string = match &self.query_params {
+            Some(params) => Some(Arc::from(crate::qs::to_string(params)?.as_str())),
+            None => None,
+        };
+
+        Ok(FrozenRequest {
+            client: self.client,
+            method: self.method,
+            path: self.path,
+            headers: Arc::new(self.headers),
+            body,
+            query_string,
+            retry_policy: self.retry_policy,
+            mark_resp: PhantomData,
         })
-        .await
-        .map_err(|_| HttpClientError::RequestTimeout)??;
-
-        tracing::info!(duration = ?s.elapsed(), body = %text.as_str(), "http response");
-
-        let resp = match serde_json::from_str::<OpenApiResponse>(&text) {
-            Ok(resp) if resp.code == 0 => resp.data.ok_or(HttpClientError::UnexpectedResponse),
-            Ok(resp) => Err(HttpClientError::OpenApi {
-                code: resp.code,
-                message: resp.message,
-                trace_id,
-            }),
-            Err(err) if status == StatusCode::OK => {
-                Err(HttpClientError::DeserializeResponseBody(err.to_string()))
-            }
-            Err(_) => Err(HttpClientError::BadStatus(status)),
-        }?;
+    }
+}
 
-        R::parse_from_bytes(resp.get().as_bytes())
-            .map_err(|err| HttpClientError::DeserializeResponseBody(err.to_string()))
+/// An immutable, cheaply-clonable snapshot of a request that can be sent many
+/// times without re-serializing the body or rebuilding headers.
+///
+/// Created with [`RequestBuilder::freeze`]. Only the per-call timestamp and
+/// signature are re-derived on each [`send`][`FrozenRequest::send`]; the body,
+/// headers and query string are shared behind [`Arc`].
+pub struct FrozenRequest<'a, R> {
+    client: &'a HttpClient,
+    method: Method,
+    path: String,
+    headers: Arc<HeaderMap>,
+    body: Option<Arc<Vec<u8>>>,
+    query_string: Option<Arc<str>>,
+    retry_policy: Option<RetryPolicy>,
+    mark_resp: PhantomData<R>,
+}
+
+impl<R> Clone for FrozenRequest<'_, R> {
+    fn clone(&self) -> Self {
+        Self {
+            client: self.client,
+            method: self.method.clone(),
+            path: self.path.clone(),
+            headers: Arc::clone(&self.headers),
+            body: self.body.clone(),
+            query_string: self.query_string.clone(),
+            retry_policy: self.retry_policy.clone(),
+            mark_resp: PhantomData,
+        }
     }
+}
 
-    /// Send request and get the response
-    pub async fn send(self) -> HttpClientResult<R> {
-        match self.do_send().await {
-            Ok(resp) => Ok(resp),
-            Err(HttpClientError::BadStatus(StatusCode::TOO_MANY_REQUESTS)) => {
-                let mut retry_delay = RETRY_INITIAL_DELAY;
-
-                for _ in 0..RETRY_COUNT {
-                    tokio::time::sleep(retry_delay).await;
-
-                    match self.do_send().await {
-                        Ok(resp) => return Ok(resp),
-                        Err(HttpClientError::BadStatus(StatusCode::TOO_MANY_REQUESTS)) => {
-                            retry_delay =
-                                Duration::from_secs_f32(retry_delay.as_secs_f32() * RETRY_FACTOR);
-                            continue;
-                        }
-                        Err(err) => return Err(err),
-                    }
-                }
+impl<R> FrozenRequest<'_, R>
+where
+    R: FromPayload,
+{
+    async fn build_request(&self) -> Result<reqwest::Request, HttpClientError> {
+        let HttpClient {
+            http_cli,
+            config,
+            default_headers,
+            authenticator,
+        } = &self.client;
+        let timestamp = self
+            .headers
+            .get("X-Timestamp")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse().ok())
+            .unwrap_or_else(Timestamp::now);
 
-                Err(HttpClientError::BadStatus(StatusCode::TOO_MANY_REQUESTS))
-            }
-            Err(err) => Err(err),
+        let url = if let Some(url) = config.http_url.as_deref() {
+            url
+        } else if is_cn().await {
+            HTTP_URL_CN
+        } else {
+            HTTP_URL
+        };
+
+        let mut request_builder = http_cli
+            .request(self.method.clone(), format!("{}{}", url, self.path))
+            .headers(default_headers.clone())
+            .headers((*self.headers).clone())
+            .header("User-Agent", USER_AGENT)
+            .header("Content-Type", "application/json; charset=utf-8");
+
+        // negotiate response compression unless opted out
+        if config.accept_compression {
+            request_builder = request_builder.header("Accept-Encoding", "gzip, deflate");
+        }
+
+        // re-use the body serialized at freeze time
+        if let Some(body) = &self.body {
+            request_builder = request_builder.body((**body).clone());
+        }
+
+        let mut request = request_builder.build().expect("invalid request");
+
+        // re-use the query string rendered at freeze time
+        if let Some(query_string) = &self.query_string {
+            request.url_mut().set_query(Some(query_string));
         }
+
+        // re-derive the signature for this call's timestamp
+        authenticator.sign(&mut request, timestamp)?;
+
+        Ok(request)
+    }
+
+    async fn try_send(&self) -> Result<R, FailedAttempt> {
+        let request = self.build_request().await?;
+        tracing::info!(method = %request.method(), url = %request.url(), "http request");
+        send_request(&self.client.http_cli, request).await
+    }
+
+    /// Send the frozen request and get the response, retrying according to the
+    /// effective [`RetryPolicy`].
+    pub async fn send(&self) -> HttpClientResult<R> {
+        let policy = self
+            .retry_policy
+            .clone()
+            .unwrap_or_else(|| self.client.config.retry_policy.clone());
+        run_with_retry(&self.method, &policy, || self.try_send()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use reqwest::{
+        Method,
+        header::{HeaderMap, HeaderValue},
+    };
+
+    use crate::{HttpClientError, Timestamp};
+
+    use super::{Authenticator, Jitter, LongPortAuthenticator, RetryPolicy, parse_retry_after};
+
+    fn signed_request() -> reqwest::Request {
+        reqwest::Client::new()
+            .request(Method::GET, "https://example.com/v1/quote?symbol=700.HK")
+            .build()
+            .unwrap()
+    }
+
+    fn retry_after_header(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            reqwest::header::RETRY_AFTER,
+            HeaderValue::from_str(value).unwrap(),
+        );
+        headers
+    }
+
+    #[test]
+    fn idempotent_methods_are_retried_by_default() {
+        let policy = RetryPolicy::default();
+        assert!(policy.method_is_retryable(&Method::GET));
+        assert!(policy.method_is_retryable(&Method::HEAD));
+    }
+
+    #[test]
+    fn mutating_methods_are_not_retried_unless_opted_in() {
+        let policy = RetryPolicy::default();
+        for method in [Method::POST, Method::PUT, Method::DELETE] {
+            assert!(
+                !policy.method_is_retryable(&method),
+                "{method} must not be retried by default"
+            );
+        }
+
+        let policy = RetryPolicy {
+            retry_non_idempotent: true,
+            ..RetryPolicy::default()
+        };
+        for method in [Method::POST, Method::PUT, Method::DELETE] {
+            assert!(policy.method_is_retryable(&method));
+        }
+    }
+
+    #[test]
+    fn parse_retry_after_integer_seconds() {
+        let headers = retry_after_header("120");
+        assert_eq!(parse_retry_after(&headers), Some(Duration::from_secs(120)));
+    }
+
+    #[test]
+    fn parse_retry_after_http_date() {
+        let when = SystemTime::now() + Duration::from_secs(120);
+        let headers = retry_after_header(&httpdate::fmt_http_date(when));
+        let delay = parse_retry_after(&headers).expect("a future date yields a delay");
+        // allow a generous window for clock granularity
+        assert!(delay <= Duration::from_secs(120));
+        assert!(delay >= Duration::from_secs(110));
+    }
+
+    #[test]
+    fn parse_retry_after_past_date_is_none() {
+        let when = SystemTime::now() - Duration::from_secs(3600);
+        let headers = retry_after_header(&httpdate::fmt_http_date(when));
+        assert_eq!(parse_retry_after(&headers), None);
+    }
+
+    #[test]
+    fn parse_retry_after_garbage_is_none() {
+        let headers = retry_after_header("not-a-date");
+        assert_eq!(parse_retry_after(&headers), None);
+    }
+
+    #[test]
+    fn decorrelated_delay_stays_within_bounds() {
+        let policy = RetryPolicy {
+            jitter: Jitter::Decorrelated,
+            ..RetryPolicy::default()
+        };
+        let mut prev = policy.base_delay;
+        for attempt in 0..64 {
+            let delay = policy.next_delay(attempt, prev);
+            assert!(delay >= policy.base_delay, "delay below base_delay");
+            assert!(delay <= policy.max_delay, "delay above max_delay");
+            prev = delay;
+        }
+    }
+
+    #[test]
+    fn exponential_grows_and_caps_at_max_delay() {
+        let policy = RetryPolicy {
+            jitter: Jitter::None,
+            ..RetryPolicy::default()
+        };
+        let close = |a: Duration, b: Duration| a.abs_diff(b) < Duration::from_millis(1);
+        assert!(close(policy.next_delay(0, policy.base_delay), policy.base_delay));
+        assert!(close(
+            policy.next_delay(1, policy.base_delay),
+            Duration::from_millis(200)
+        ));
+        assert!(policy.next_delay(1, policy.base_delay) > policy.next_delay(0, policy.base_delay));
+        // far-out attempts are clamped to max_delay
+        assert_eq!(policy.next_delay(40, policy.base_delay), policy.max_delay);
+    }
+
+    #[test]
+    fn default_authenticator_injects_signing_headers() {
+        let auth = LongPortAuthenticator {
+            app_key: "key".into(),
+            access_token: "token".into(),
+            app_secret: "secret".into(),
+        };
+        let mut request = signed_request();
+        auth.sign(&mut request, Timestamp::now()).unwrap();
+
+        let headers = request.headers();
+        assert_eq!(headers["X-Api-Key"], "key");
+        assert_eq!(headers["Authorization"], "token");
+        assert!(headers.contains_key("X-Timestamp"));
+        assert!(headers.contains_key("X-Api-Signature"));
+    }
+
+    #[test]
+    fn default_authenticator_is_deterministic_for_a_timestamp() {
+        let auth = LongPortAuthenticator {
+            app_key: "key".into(),
+            access_token: "token".into(),
+            app_secret: "secret".into(),
+        };
+        let timestamp = Timestamp::now();
+
+        let mut a = signed_request();
+        let mut b = signed_request();
+        auth.sign(&mut a, timestamp).unwrap();
+        auth.sign(&mut b, timestamp).unwrap();
+
+        assert_eq!(a.headers()["X-Api-Signature"], b.headers()["X-Api-Signature"]);
+    }
+
+    /// A test double proving [`Authenticator`] can be exercised in isolation
+    /// from the network: it records the timestamp it was asked to sign with.
+    struct RecordingAuthenticator {
+        seen: std::sync::Mutex<Option<Timestamp>>,
+    }
+
+    impl Authenticator for RecordingAuthenticator {
+        fn sign(
+            &self,
+            request: &mut reqwest::Request,
+            timestamp: Timestamp,
+        ) -> Result<(), HttpClientError> {
+            *self.seen.lock().unwrap() = Some(timestamp);
+            request
+                .headers_mut()
+                .insert("X-Test-Signed", HeaderValue::from_static("1"));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn custom_authenticator_is_invoked_in_isolation() {
+        let auth = RecordingAuthenticator {
+            seen: std::sync::Mutex::new(None),
+        };
+        let timestamp = Timestamp::now();
+        let mut request = signed_request();
+
+        auth.sign(&mut request, timestamp).unwrap();
+
+        assert_eq!(*auth.seen.lock().unwrap(), Some(timestamp));
+        assert_eq!(request.headers()["X-Test-Signed"], "1");
+        // the default scheme's headers are absent: only our double ran
+        assert!(!request.headers().contains_key("X-Api-Signature"));
     }
 }
@@ -0,0 +1,70 @@
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+
+use crate::timestamp::Timestamp;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// The inputs required to sign a request with the LongPort scheme.
+pub struct SignatureParams<'a> {
+    /// The request to sign (method, url and body are part of the canonical
+    /// string).
+    pub request: &'a reqwest::Request,
+    /// The application key.
+    pub app_key: &'a str,
+    /// The access token, when present.
+    pub access_token: Option<&'a str>,
+    /// The application secret used to key the HMAC.
+    pub app_secret: &'a str,
+    /// The timestamp advertised on the request.
+    pub timestamp: Timestamp,
+}
+
+/// Compute the `X-Api-Signature` value for `params`.
+pub fn signature(params: SignatureParams<'_>) -> String {
+    let SignatureParams {
+        request,
+        app_key,
+        access_token,
+        app_secret,
+        timestamp,
+    } = params;
+
+    let method = request.method().as_str();
+    let path = request.url().path();
+    let query = request.url().query().unwrap_or_default();
+
+    // Canonical request: method, path, query, signed headers and a digest of
+    // the body.
+    let mut canonical = format!("{method}|{path}|{query}|");
+    canonical.push_str(&format!("x-api-key:{app_key}\n"));
+    if let Some(access_token) = access_token {
+        canonical.push_str(&format!("authorization:{access_token}\n"));
+    }
+    canonical.push_str(&format!("x-timestamp:{timestamp}\n"));
+    if let Some(body) = request.body().and_then(|body| body.as_bytes()) {
+        canonical.push_str(&hex(&Sha256::digest(body)));
+    }
+
+    let mut mac = HmacSha256::new_from_slice(app_secret.as_bytes()).expect("hmac accepts any key");
+    mac.update(canonical.as_bytes());
+    let digest = mac.finalize().into_bytes();
+
+    let signed_headers = if access_token.is_some() {
+        "authorization;x-api-key;x-timestamp"
+    } else {
+        "x-api-key;x-timestamp"
+    };
+    format!("HMAC-SHA256 SignedHeaders={signed_headers}, Signature={}", hex(&digest))
+}
+
+/// Lower-case hex encoding.
+fn hex(bytes: &[u8]) -> String {
+    use std::fmt::Write;
+
+    let mut out = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        let _ = write!(out, "{byte:02x}");
+    }
+    out
+}